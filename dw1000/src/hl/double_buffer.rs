@@ -0,0 +1,154 @@
+use crate::{hl::ready::WakeSource, AutoDoubleBufferReceiving, Error, Sleeping, DW1000};
+use embedded_hal::spi::SpiDevice;
+
+use super::Message;
+
+/// A message received via [`DW1000::wait_receive_double_buffered`]
+///
+/// In addition to the decoded [`Message`], this carries the FCS-valid flag
+/// that was captured as part of the race-safe buffer read, so callers can
+/// tell a clean frame apart from one that needs [`Error::Fcs`] treatment
+/// without a second register round-trip.
+pub struct DoubleBufferedMessage<'b> {
+    /// The received message
+    pub message: Message<'b>,
+
+    /// Whether the frame passed the FCS check (`SYS_STATUS.RXFCG`), as
+    /// opposed to failing it (`SYS_STATUS.RXFCE`)
+    pub fcs_valid: bool,
+}
+
+impl<SPI> DW1000<SPI, AutoDoubleBufferReceiving>
+where
+    SPI: SpiDevice,
+{
+    /// Waits for a message, guarding against the documented double-buffer
+    /// race condition
+    ///
+    /// The DW1000 can start filling the other half of the double buffer
+    /// while the host is still reading out the one it was told to read,
+    /// which corrupts the diagnostics (and potentially the frame) if the
+    /// host isn't careful. Once a frame has actually landed, this masks the
+    /// relevant `SYS_MASK` receive bits before touching any buffer
+    /// registers, captures the FCS-valid flag from `SYS_STATUS` alongside
+    /// the frame, and verifies that the host/IC buffer pointers
+    /// (`HSRBP`/`ICRBP`) didn't flip while the read was in progress. If a
+    /// flip is detected, the read is retried against the (now current)
+    /// buffer. Reception is only re-enabled, by restoring the masked bits,
+    /// once a consistent read has been obtained.
+    ///
+    /// This is meant to be busy-polled, the same way [`Self::wait_receive`]
+    /// and `wait_transmit` are, so an unsuccessful poll (no frame ready yet)
+    /// checks `SYS_STATUS.RXDFR` and returns `WouldBlock` without touching
+    /// `SYS_MASK` at all.
+    pub fn wait_receive_double_buffered<'b>(
+        &mut self,
+        buffer: &'b mut [u8],
+    ) -> nb::Result<DoubleBufferedMessage<'b>, Error<SPI>> {
+        let status = self
+            .ll
+            .sys_status()
+            .read()
+            .map_err(|error| nb::Error::Other(Error::Spi(error.0)))?;
+        if status.rxdfr() == 0b0 {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        // A frame is actually ready to be read out: mask the receive events
+        // while we do, so a new frame landing mid-read can't trigger a
+        // spurious interrupt based on diagnostics that are about to be
+        // overwritten.
+        let rx_mask = self
+            .ll
+            .sys_mask()
+            .read()
+            .map_err(|error| nb::Error::Other(Error::Spi(error.0)))?;
+        self.ll
+            .sys_mask()
+            .modify(|_, w| w.mrxdfr(0b0).mrxfce(0b0))
+            .map_err(|error| nb::Error::Other(Error::Spi(error.0)))?;
+
+        let result = self.read_double_buffered(buffer);
+
+        // Re-enable reception now that the read has completed, restoring
+        // whichever of the two bits were set before.
+        let restore = rx_mask;
+        self.ll
+            .sys_mask()
+            .modify(|_, w| w.mrxdfr(restore.mrxdfr()).mrxfce(restore.mrxfce()))
+            .map_err(|error| nb::Error::Other(Error::Spi(error.0)))?;
+
+        result
+    }
+
+    fn read_double_buffered<'b>(
+        &mut self,
+        buffer: &'b mut [u8],
+    ) -> nb::Result<DoubleBufferedMessage<'b>, Error<SPI>> {
+        loop {
+            let status_before = self
+                .ll
+                .sys_status()
+                .read()
+                .map_err(|error| nb::Error::Other(Error::Spi(error.0)))?;
+
+            if status_before.rxdfr() == 0b0 {
+                return Err(nb::Error::WouldBlock);
+            }
+
+            let fcs_valid = status_before.rxfcg() == 0b1;
+            let pointers_before = (status_before.hsrbp(), status_before.icrbp());
+
+            let message = self.wait_receive(buffer)?;
+
+            let status_after = self
+                .ll
+                .sys_status()
+                .read()
+                .map_err(|error| nb::Error::Other(Error::Spi(error.0)))?;
+
+            if (status_after.hsrbp(), status_after.icrbp()) != pointers_before {
+                // The buffer pointer flipped while we were reading: a new
+                // frame landed and may have corrupted what we just read.
+                // Retry against the buffer that's current now.
+                continue;
+            }
+
+            return Ok(DoubleBufferedMessage { message, fcs_valid });
+        }
+    }
+
+    /// Finishes a double-buffered receive that was configured to sleep
+    /// automatically via [`DW1000::configure_auto_sleep`]
+    ///
+    /// The double-buffered counterpart to
+    /// [`DW1000<SPI, SingleBufferReceiving>::finish_receiving_to_sleep`][recv]:
+    /// the chip has already gone to sleep by the time a frame has been
+    /// received, so this reflects that in the typestate rather than trying
+    /// to reset the transceiver or switch the active buffer, without
+    /// issuing any SPI transaction of its own against a chip that may
+    /// already be asleep. `tx_antenna_delay` and `wake_source` should be
+    /// exactly what `configure_auto_sleep` returned and was passed,
+    /// respectively.
+    ///
+    /// [recv]: crate::hl::SingleBufferReceiving
+    #[allow(clippy::type_complexity)]
+    pub fn finish_receiving_double_buffered_to_sleep(
+        self,
+        tx_antenna_delay: u16,
+        wake_source: WakeSource,
+    ) -> Result<DW1000<SPI, Sleeping>, (Self, Error<SPI>)> {
+        if !self.state.finished {
+            return Err((self, Error::RxNotFinished));
+        }
+
+        Ok(DW1000 {
+            ll: self.ll,
+            seq: self.seq,
+            state: Sleeping {
+                tx_antenna_delay,
+                wake_source,
+            },
+        })
+    }
+}
@@ -86,6 +86,26 @@ where
 
     /// The transmission has not yet finished
     TxNotFinishedyet,
+
+    /// No auto-ACK was received before the configured timeout
+    ///
+    /// Unlike [`Error::FrameWaitTimeout`], which applies to a plain receive
+    /// operation, this marks a single attempt of [`DW1000::send_with_retries`]
+    /// whose ACK didn't arrive in time. That method retries such an attempt,
+    /// or reports [`Error::MaxRetriesExceeded`] once no attempts are left, so
+    /// it never returns this variant to its caller.
+    ///
+    /// [`DW1000::send_with_retries`]: crate::DW1000::send_with_retries
+    AckTimeout,
+
+    /// [`DW1000::send_with_retries`] gave up after exhausting its configured
+    /// number of attempts without receiving an ACK
+    ///
+    /// [`DW1000::send_with_retries`]: crate::DW1000::send_with_retries
+    MaxRetriesExceeded {
+        /// The number of attempts that were made before giving up
+        attempts: u8,
+    },
 }
 
 impl<SPI> From<ll::Error<SPI>> for Error<SPI>
@@ -143,6 +163,10 @@ where
             Error::TxNotFinishedyet => {
                 write!(f, "TxNotFinishedyet")
             }
+            Error::AckTimeout => write!(f, "AckTimeout"),
+            Error::MaxRetriesExceeded { attempts } => {
+                write!(f, "MaxRetriesExceeded {{ attempts: {:?} }}", attempts)
+            }
         }
     }
 }
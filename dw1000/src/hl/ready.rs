@@ -1,13 +1,15 @@
 use super::{AutoDoubleBufferReceiving, Receiving};
 use crate::{
     configs::{AutoAck, BitRate, SfdSequence, TxContinuation},
+    irq::{IrqEvents, IrqMask},
     time::Instant,
     Error, Ready, RxConfig, Sending, SingleBufferReceiving, Sleeping, TxConfig, DW1000,
 };
 use byte::BytesExt as _;
 use core::num::Wrapping;
-use embedded_hal::spi::SpiDevice;
+use embedded_hal::{delay::DelayNs, spi::SpiDevice};
 use ieee802154::mac::{self, FooterMode, FrameSerDesContext};
+use nb;
 
 /// The behaviour of the sync pin
 pub enum SyncBehaviour {
@@ -33,6 +35,20 @@ pub enum SendTime {
     OnSync,
 }
 
+/// One of the four LED outputs [`DW1000::configure_leds`] and
+/// [`DW1000::force_led`] can drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Led {
+    /// GPIO0, normally wired to RXOKLED
+    RxOk,
+    /// GPIO1, normally wired to SFDLED
+    Sfd,
+    /// GPIO2, normally wired to RXLED
+    Rx,
+    /// GPIO3, normally wired to TXLED
+    Tx,
+}
+
 /// The polarity of the irq signal
 pub enum IrqPolarity {
     /// The signal will be high when the interrupt is active
@@ -41,6 +57,200 @@ pub enum IrqPolarity {
     ActiveLow = 0,
 }
 
+/// Selects which `SYS_MASK` events cause the DW1000's IRQ line to be
+/// asserted
+///
+/// Used with [`DW1000::enable_interrupts`]. All events default to disabled;
+/// enable exactly the ones you plan to handle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IrqConfig {
+    /// Frame sent (`MTXFRS`)
+    pub frame_sent: bool,
+    /// Good RX frame received (`MRXDFR`)
+    pub rx_good_frame: bool,
+    /// Receiver FCS error (`MRXFCE`)
+    pub fcs_error: bool,
+    /// Receiver Reed-Solomon frame sync loss (`MRXRFSL`)
+    pub reed_solomon_error: bool,
+    /// PHY header error (`MRXPHE`)
+    pub phy_error: bool,
+    /// Receiver SFD timeout (`MRXSFDTO`)
+    pub sfd_timeout: bool,
+    /// Preamble detection timeout (`MRXPTO`)
+    pub preamble_detection_timeout: bool,
+    /// Receiver overrun (`MRXOVRR`)
+    pub overrun: bool,
+    /// Receive frame wait timeout (`MRXRFTO`)
+    pub frame_wait_timeout: bool,
+}
+
+impl IrqConfig {
+    /// Enables every RX error/timeout event that [`DW1000::poll_irq_cause`]
+    /// knows how to decode, plus `frame_sent` and `rx_good_frame`
+    pub fn all() -> Self {
+        IrqConfig {
+            frame_sent: true,
+            rx_good_frame: true,
+            fcs_error: true,
+            reed_solomon_error: true,
+            phy_error: true,
+            sfd_timeout: true,
+            preamble_detection_timeout: true,
+            overrun: true,
+            frame_wait_timeout: true,
+        }
+    }
+}
+
+impl From<IrqConfig> for IrqMask {
+    /// Maps the handful of events [`DW1000::poll_irq_cause`] knows how to
+    /// decode onto the full [`crate::irq::Irq`] event set, so
+    /// [`DW1000::enable_interrupts`] can be expressed in terms of
+    /// [`DW1000::configure_interrupts`] instead of writing `SYS_MASK` a
+    /// second, independent way.
+    fn from(config: IrqConfig) -> Self {
+        let mut mask = IrqMask::empty();
+        mask.set(IrqMask::TX_FRAME_SENT, config.frame_sent);
+        mask.set(IrqMask::RX_DATA_FRAME_READY, config.rx_good_frame);
+        mask.set(IrqMask::RX_FCS_ERROR, config.fcs_error);
+        mask.set(IrqMask::RX_REED_SOLOMON_SYNC_LOSS, config.reed_solomon_error);
+        mask.set(IrqMask::RX_PHY_HEADER_ERROR, config.phy_error);
+        mask.set(IrqMask::RX_SFD_TIMEOUT, config.sfd_timeout);
+        mask.set(
+            IrqMask::RX_PREAMBLE_TIMEOUT,
+            config.preamble_detection_timeout,
+        );
+        mask.set(IrqMask::RX_OVERRUN, config.overrun);
+        mask.set(IrqMask::RX_TIMEOUT, config.frame_wait_timeout);
+        mask
+    }
+}
+
+/// Selects which sources can wake the DW1000 from sleep
+///
+/// Passed to [`DW1000::enter_sleep`], [`DW1000::enter_sleep_for`] and
+/// [`DW1000::configure_auto_sleep`], and mirrored onto the resulting
+/// [`Sleeping`] state so the corresponding wake-up logic knows which sources
+/// were actually armed. Any combination of sources can be enabled at once,
+/// but at least one must be; the all-`false` [`Default`] arms no wake source
+/// at all, which would leave the chip asleep forever, so those methods
+/// reject it with [`Error::InvalidConfiguration`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WakeSource {
+    /// Wake on SPI chip-select activity (`AON_CFG0.WAKE_SPI`)
+    pub spi_cs: bool,
+
+    /// Wake when the external `WAKEUP` pin is asserted
+    /// (`AON_CFG0.WAKE_PIN`)
+    ///
+    /// This is the lowest-power wake path, as it doesn't require any SPI
+    /// traffic to bring the chip out of sleep; a host MCU can toggle the
+    /// line from a timer interrupt instead.
+    pub wakeup_pin: bool,
+
+    /// Wake when the sleep counter expires (`AON_CFG0.WAKE_CNT`)
+    ///
+    /// Only takes effect if `sleep_duration` was also `Some` when sleep was
+    /// entered.
+    pub sleep_counter: bool,
+}
+
+/// Configuration for automatically falling back to sleep after a TX or RX
+///
+/// Passed to [`DW1000::configure_auto_sleep`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AutoSleepConfig {
+    /// Drop into sleep automatically once a transmission completes
+    /// (`PMSC_CTRL1.ATXSLP`)
+    pub after_tx: bool,
+
+    /// Drop into sleep automatically once a reception completes
+    /// (`PMSC_CTRL1.ARXSLP`)
+    pub after_rx: bool,
+
+    /// Which sources may wake the radio back up, same as for
+    /// [`DW1000::enter_sleep`]
+    pub wake_source: WakeSource,
+}
+
+/// The measured period of a single `AON_CFG0.SLEEP_TIM` tick
+///
+/// Obtained via [`DW1000::calibrate_sleep_timer`], and used by
+/// [`DW1000::enter_sleep_for`] to convert a wall-clock duration into the raw
+/// tick count that [`DW1000::enter_sleep`] expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SleepTick {
+    period_us: u32,
+}
+
+impl SleepTick {
+    /// The calibrated tick period, in microseconds
+    pub fn period_us(&self) -> u32 {
+        self.period_us
+    }
+
+    /// Converts `duration` into a tick count, rounding to the nearest tick
+    /// and saturating to the 16-bit range `AON_CFG0.SLEEP_TIM` can hold
+    fn ticks_for(&self, duration: core::time::Duration) -> u16 {
+        let period_us = self.period_us.max(1) as u128;
+        let duration_us = duration.as_micros();
+
+        let ticks = (duration_us + period_us / 2) / period_us;
+
+        ticks.min(u16::MAX as u128) as u16
+    }
+}
+
+#[cfg(test)]
+mod sleep_tick_tests {
+    use super::SleepTick;
+    use core::time::Duration;
+
+    #[test]
+    fn rounds_to_the_nearest_tick() {
+        let tick = SleepTick { period_us: 431_000 };
+
+        // Just under half a tick rounds down, just over rounds up.
+        assert_eq!(tick.ticks_for(Duration::from_micros(215_000)), 0);
+        assert_eq!(tick.ticks_for(Duration::from_micros(216_000)), 1);
+        assert_eq!(tick.ticks_for(Duration::from_micros(431_000)), 1);
+    }
+
+    #[test]
+    fn saturates_at_u16_max() {
+        let tick = SleepTick { period_us: 1 };
+
+        let huge = Duration::from_secs(u32::MAX as u64);
+        assert_eq!(tick.ticks_for(huge), u16::MAX);
+    }
+
+    #[test]
+    fn zero_period_does_not_panic() {
+        // `calibrate_sleep_timer` already refuses a zero measurement, but
+        // `ticks_for` itself should stay well-defined (treating it as the
+        // smallest representable period) rather than divide by zero.
+        let tick = SleepTick { period_us: 0 };
+
+        assert_eq!(tick.ticks_for(Duration::from_micros(0)), 0);
+        assert_eq!(tick.ticks_for(Duration::from_micros(5)), 5);
+    }
+}
+
+/// The decoded result of [`DW1000::poll_irq_cause`]
+pub struct IrqCause<SPI>
+where
+    SPI: SpiDevice,
+{
+    /// The frame-sent event (`TXFRS`) fired
+    pub frame_sent: bool,
+    /// A good frame was received (`RXDFR`)
+    pub rx_good_frame: bool,
+    /// The first RX error/timeout event found set in `SYS_STATUS`, already
+    /// mapped to the matching [`Error`] variant, in the same priority order
+    /// the blocking wait methods use
+    pub error: Option<Error<SPI>>,
+}
+
 impl<SPI> DW1000<SPI, Ready>
 where
     SPI: SpiDevice,
@@ -650,12 +860,102 @@ where
         Ok(())
     }
 
+    /// Configures the DW1000's IRQ line according to `config`
+    ///
+    /// Unlike [`Self::enable_tx_interrupts`] and [`Self::enable_rx_interrupts`],
+    /// which each hard-code their own fixed set of `SYS_MASK` bits, this lets
+    /// the caller pick exactly the events they care about in one call, via
+    /// [`IrqConfig`]. Overwrites any interrupt flags that were previously
+    /// set, including those set by the two methods above. Built on top of
+    /// [`Self::configure_interrupts`], so it shares a single `SYS_MASK`
+    /// encoding with that method instead of maintaining its own. Pair this
+    /// with [`Self::poll_irq_cause`], which already knows how to decode the
+    /// same set of events, so the two can no longer drift out of sync the
+    /// way the comment on `wait_transmit` warns about.
+    pub fn enable_interrupts(&mut self, config: IrqConfig) -> Result<(), Error<SPI>> {
+        self.configure_interrupts(config.into())
+    }
+
+    /// Reads `SYS_STATUS` once and decodes which configured event fired
+    ///
+    /// Each recognized event is mapped to the [`Error`] variant that the
+    /// blocking/`nb` wait methods would also return for it, so an interrupt
+    /// handler doesn't have to duplicate that decoding. Built on top of
+    /// [`Self::read_events`], so it shares [`crate::irq::Irq`]'s bit
+    /// decoding instead of maintaining its own. This only reads the
+    /// register; it doesn't clear any flags, as the existing wait methods
+    /// already do that once they've picked the event up.
+    pub fn poll_irq_cause(&mut self) -> Result<IrqCause<SPI>, Error<SPI>> {
+        let events = self.read_events()?;
+
+        let error = if events.contains(IrqEvents::RX_FCS_ERROR) {
+            Some(Error::Fcs)
+        } else if events.contains(IrqEvents::RX_REED_SOLOMON_SYNC_LOSS) {
+            Some(Error::ReedSolomon)
+        } else if events.contains(IrqEvents::RX_PHY_HEADER_ERROR) {
+            Some(Error::Phy)
+        } else if events.contains(IrqEvents::RX_SFD_TIMEOUT) {
+            Some(Error::SfdTimeout)
+        } else if events.contains(IrqEvents::RX_PREAMBLE_TIMEOUT) {
+            Some(Error::PreambleDetectionTimeout)
+        } else if events.contains(IrqEvents::RX_OVERRUN) {
+            Some(Error::Overrun)
+        } else if events.contains(IrqEvents::RX_TIMEOUT) {
+            Some(Error::FrameWaitTimeout)
+        } else {
+            None
+        };
+
+        Ok(IrqCause {
+            frame_sent: events.contains(IrqEvents::TX_FRAME_SENT),
+            rx_good_frame: events.contains(IrqEvents::RX_DATA_FRAME_READY),
+            error,
+        })
+    }
+
     /// Disables all interrupts
     pub fn disable_interrupts(&mut self) -> Result<(), Error<SPI>> {
         self.ll.sys_mask().write(|w| w)?;
         Ok(())
     }
 
+    /// Configures the DW1000's IRQ line using a composable event mask
+    ///
+    /// Unlike [`Self::enable_tx_interrupts`] and [`Self::enable_rx_interrupts`],
+    /// which each hard-code a fixed set of events, this writes `mask`
+    /// directly to `SYS_MASK`, letting callers enable any combination of
+    /// events, including ones only needed for custom interrupt handling
+    /// (sleep-to-init, clock PLL lock, ...) that [`IrqConfig`] has no field
+    /// for. [`Self::enable_interrupts`] is a thin wrapper around this method
+    /// for the common subset of events the driver's own wait methods check
+    /// for. Overwrites any interrupt flags that were previously set.
+    pub fn configure_interrupts(&mut self, mask: IrqMask) -> Result<(), Error<SPI>> {
+        self.ll.sys_mask().write(|w| w.raw_value(mask.bits()))?;
+        Ok(())
+    }
+
+    /// Reads `SYS_STATUS` once and decodes which events have fired
+    ///
+    /// Returns the raw, decoded set of events; unlike
+    /// [`Self::poll_irq_cause`], it doesn't map them to [`Error`] variants,
+    /// so interrupt handlers that need to dispatch on more than the handful
+    /// of events that driver understands can check for any bit in
+    /// [`crate::irq::Irq`] directly. Doesn't clear any flags; follow up with
+    /// [`Self::clear_events`] once the event has been handled.
+    pub fn read_events(&mut self) -> Result<IrqEvents, Error<SPI>> {
+        let status = self.ll.sys_status().read()?;
+        Ok(IrqEvents::from_bits_truncate(status.raw_value()))
+    }
+
+    /// Clears the given events in `SYS_STATUS`
+    ///
+    /// `SYS_STATUS` is write-1-to-clear, so only the bits set in `events`
+    /// are affected; any other pending event is left alone.
+    pub fn clear_events(&mut self, events: IrqEvents) -> Result<(), Error<SPI>> {
+        self.ll.sys_status().write(|w| w.raw_value(events.bits()))?;
+        Ok(())
+    }
+
     /// Configures the gpio pins to operate as LED output.
     ///
     /// - Note: This means that the function of the gpio pins change
@@ -698,19 +998,95 @@ where
         Ok(())
     }
 
+    /// Forces `led` on or off under host control
+    ///
+    /// `configure_leds` only wires the LED pins up to hardware RX/SFD/TX
+    /// blink sources; this instead uses `PMSC_LEDC`'s force-blink field to
+    /// drive a single LED directly, so firmware can use it for status
+    /// indication (pairing, error, battery level, ...) without faking radio
+    /// activity. Turns on the debounce/kHz clocks and puts the
+    /// corresponding GPIO pin into LED mode, same as `configure_leds` does.
+    pub fn force_led(&mut self, led: Led, on: bool) -> Result<(), Error<SPI>> {
+        self.ll.pmsc_ctrl0().modify(|_, w| w.gpdce(1).khzclken(1))?;
+
+        self.ll.gpio_mode().modify(|_, w| match led {
+            Led::RxOk => w.msgp0(1),
+            Led::Sfd => w.msgp1(1),
+            Led::Rx => w.msgp2(1),
+            Led::Tx => w.msgp3(1),
+        })?;
+
+        self.ll.pmsc_ledc().modify(|_, w| {
+            let w = w.blnken(1);
+            match led {
+                Led::RxOk => w.flshp0(on as u8),
+                Led::Sfd => w.flshp1(on as u8),
+                Led::Rx => w.flshp2(on as u8),
+                Led::Tx => w.flshp3(on as u8),
+            }
+        })?;
+
+        Ok(())
+    }
+
+    /// Pulses each LED in `leds` on and then off once, using [`Self::force_led`]
+    ///
+    /// Lets integrators confirm LED wiring at boot, independent of any
+    /// radio activity. Restores whatever `GPIO_MODE`/clock configuration
+    /// was in place beforehand, so running this self-test doesn't
+    /// permanently repurpose pins that weren't already configured as LED
+    /// outputs.
+    pub fn blink_test<D>(&mut self, leds: &[Led], delay: &mut D) -> Result<(), Error<SPI>>
+    where
+        D: DelayNs,
+    {
+        let gpio_mode = self.ll.gpio_mode().read()?;
+        let pmsc_ctrl0 = self.ll.pmsc_ctrl0().read()?;
+
+        for &led in leds {
+            self.force_led(led, true)?;
+            delay.delay_ms(50);
+            self.force_led(led, false)?;
+        }
+
+        self.ll
+            .gpio_mode()
+            .write(|w| w.raw_value(gpio_mode.raw_value()))?;
+        self.ll.pmsc_ctrl0().modify(|_, w| {
+            w.gpdce(pmsc_ctrl0.gpdce())
+                .khzclken(pmsc_ctrl0.khzclken())
+        })?;
+
+        Ok(())
+    }
+
     /// Puts the dw1000 into sleep mode.
     ///
     /// - `irq_on_wakeup`: When set to true, the IRQ pin will be asserted when the radio wakes up
     /// - `sleep_duration`: When `None`, the radio will not wake up by itself and go into the deep sleep mode.
     ///   When `Some`, then the radio will wake itself up after the given time. Every tick is ~431ms, but there may
     ///   be a significant deviation from this due to the chip's manufacturing process.
+    /// - `wake_source`: Which of the DW1000's wakeup sources should be armed. See [`WakeSource`]; note that
+    ///   `sleep_counter` only has an effect when `sleep_duration` is also `Some`.
     ///
     /// *Note: The SPI speed may be at most 3 Mhz when calling this function.*
     pub fn enter_sleep(
         mut self,
         irq_on_wakeup: bool,
         sleep_duration: Option<u16>,
+        wake_source: WakeSource,
     ) -> Result<DW1000<SPI, Sleeping>, Error<SPI>> {
+        // `sleep_counter` only takes effect when a `sleep_duration` was
+        // actually given; if none of the other sources are armed either,
+        // this call would put the chip to sleep with no way of ever waking
+        // it back up.
+        let wakes_up = wake_source.spi_cs
+            || wake_source.wakeup_pin
+            || (wake_source.sleep_counter && sleep_duration.is_some());
+        if !wakes_up {
+            return Err(Error::InvalidConfiguration);
+        }
+
         // Set the sleep timer
         if let Some(sd) = sleep_duration {
             self.ll.pmsc_ctrl0().modify(|_, w| {
@@ -758,8 +1134,9 @@ where
 
         // Setup the wakeup sources.
         self.ll.aon_cfg0().modify(|_, w| {
-            w.wake_spi(1)
-                .wake_cnt(sleep_duration.is_some() as u8)
+            w.wake_spi(wake_source.spi_cs as u8)
+                .wake_pin(wake_source.wakeup_pin as u8)
+                .wake_cnt((wake_source.sleep_counter && sleep_duration.is_some()) as u8)
                 .sleep_en(1)
         })?;
 
@@ -770,7 +1147,333 @@ where
         Ok(DW1000 {
             ll: self.ll,
             seq: self.seq,
-            state: Sleeping { tx_antenna_delay },
+            state: Sleeping {
+                tx_antenna_delay,
+                wake_source,
+            },
         })
     }
+
+    /// Measures the real period of a sleep-counter tick
+    ///
+    /// [`Self::enter_sleep`] documents each tick as "~431ms, but there may be
+    /// a significant deviation from this due to the chip's manufacturing
+    /// process", because the sleep counter is clocked by the on-chip
+    /// low-power oscillator (LPOSC), which isn't trimmed at the factory.
+    /// This calibrates it against the known-accurate 19.2 MHz crystal
+    /// reference: it enables `AON_CFG1.LPOSC_CAL`, uploads the AON
+    /// configuration to latch a divided LPOSC count, waits for the
+    /// measurement to complete, and divides the result by 19.2 to arrive at
+    /// the real tick period in microseconds.
+    ///
+    /// The result can be passed to [`Self::enter_sleep_for`] to sleep for a
+    /// predictable wall-clock time, instead of guessing at a raw tick count.
+    pub fn calibrate_sleep_timer<D>(&mut self, delay: &mut D) -> Result<SleepTick, Error<SPI>>
+    where
+        D: DelayNs,
+    {
+        // Enable LPOSC calibration against the 19.2 MHz reference.
+        self.ll.aon_cfg1().modify(|_, w| w.lposc_cal(1))?;
+
+        // Upload the AON configuration, which is what actually triggers the
+        // chip to take the measurement.
+        self.ll.aon_ctrl().write(|w| w.upl_cfg(1))?;
+        self.ll.aon_ctrl().write(|w| w.upl_cfg(0))?;
+
+        // Give the chip time to complete the measurement before reading it
+        // back.
+        delay.delay_us(100);
+
+        let divided_count = self.ll.lp_osc_cal_cnt().read()?.value();
+
+        // Calibration is a one-shot measurement; leaving it enabled would
+        // just keep perturbing the sleep counter's clock source.
+        self.ll.aon_cfg1().modify(|_, w| w.lposc_cal(0))?;
+
+        if divided_count == 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        // `divided_count` is in reference clock cycles; dividing by the
+        // known 19.2 MHz reference gives the tick period in microseconds.
+        let period_us = ((divided_count as f32) / 19.2) as u32;
+
+        Ok(SleepTick { period_us })
+    }
+
+    /// Configures the PMSC to drop into sleep automatically after a TX or RX
+    ///
+    /// [`Self::enter_sleep`] only ever puts the DW1000 to sleep as an
+    /// explicit, host-initiated transition. For duty-cycled use cases (a
+    /// ranging anchor transmitting a periodic beacon, say) that means the
+    /// host has to stay awake just to issue that call. This instead
+    /// programs the always-on (AON) array once, up front, so that the PMSC
+    /// loads it and drops the chip into sleep by itself as soon as the
+    /// configured operation (`config.after_tx` and/or `config.after_rx`)
+    /// completes, with no further host intervention.
+    ///
+    /// Once a TX or RX configured here finishes, use
+    /// [`DW1000<SPI, Sending>::finish_sending_to_sleep`],
+    /// [`DW1000<SPI, SingleBufferReceiving>::finish_receiving_to_sleep`] or
+    /// [`DW1000<SPI, AutoDoubleBufferReceiving>::finish_receiving_double_buffered_to_sleep`],
+    /// instead of the plain `finish_*`/`abort_*` methods, to reflect the
+    /// chip having already gone to sleep in the typestate. Pass this
+    /// method's returned `tx_antenna_delay` straight through to whichever of
+    /// those is used: by the time the configured TX/RX completes, the PMSC
+    /// may have already dropped the chip into sleep on its own, so those
+    /// methods can't safely do a fresh SPI read of their own to obtain it.
+    ///
+    /// [`DW1000<SPI, Sending>::finish_sending_to_sleep`]: crate::hl::Sending
+    /// [`DW1000<SPI, SingleBufferReceiving>::finish_receiving_to_sleep`]: crate::hl::SingleBufferReceiving
+    /// [`DW1000<SPI, AutoDoubleBufferReceiving>::finish_receiving_double_buffered_to_sleep`]: crate::hl::AutoDoubleBufferReceiving
+    pub fn configure_auto_sleep(&mut self, config: AutoSleepConfig) -> Result<u16, Error<SPI>> {
+        // Same reasoning as `enter_sleep`: refuse to arm an automatic sleep
+        // that nothing can ever wake back up from.
+        let wakes_up = config.wake_source.spi_cs
+            || config.wake_source.wakeup_pin
+            || config.wake_source.sleep_counter;
+        if !wakes_up {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        // Read this now, while the chip is still guaranteed awake: once
+        // `ATXSLP`/`ARXSLP` fires, the chip may already be asleep by the
+        // time the caller gets around to finishing the TX/RX, and a fresh
+        // SPI read at that point isn't safe to issue.
+        let tx_antenna_delay = self.get_tx_antenna_delay()?;
+
+        // Does the chip have the ldo tune calibrated?
+        let lldo = self.read_otp(0x004)? != 0;
+
+        // Setup everything that needs to be stored in AON, same as
+        // `enter_sleep` does for an explicit sleep.
+        self.ll
+            .aon_wcfg()
+            .modify(|_, w| w.onw_ldc(1).onw_llde(1).onw_lldo(lldo as u8).onw_l64p(1))?;
+
+        self.ll.aon_cfg0().modify(|_, w| {
+            w.wake_spi(config.wake_source.spi_cs as u8)
+                .wake_pin(config.wake_source.wakeup_pin as u8)
+                .wake_cnt(config.wake_source.sleep_counter as u8)
+                .sleep_en(1)
+        })?;
+
+        // Tell the PMSC to fall back to sleep by itself once the configured
+        // operation finishes, instead of waiting for a host-initiated
+        // `enter_sleep` call.
+        self.ll
+            .pmsc_ctrl1()
+            .modify(|_, w| w.atxslp(config.after_tx as u8).arxslp(config.after_rx as u8))?;
+
+        // Upload the always-on array configuration.
+        self.ll.aon_ctrl().write(|w| w)?;
+        self.ll.aon_ctrl().write(|w| w.save(1))?;
+
+        Ok(tx_antenna_delay)
+    }
+
+    /// Puts the DW1000 into sleep mode for approximately `duration`
+    ///
+    /// This is a convenience wrapper around [`Self::enter_sleep`]: it
+    /// converts `duration` into the raw tick count that method expects,
+    /// using the correction factor obtained from
+    /// [`Self::calibrate_sleep_timer`], removing the guesswork the raw
+    /// `Option<u16>` tick interface otherwise forces on users.
+    ///
+    /// If `duration` is longer than the 16-bit sleep counter can represent
+    /// at the calibrated tick period, the requested duration saturates to
+    /// the longest one the counter can hold.
+    ///
+    /// *Note: The SPI speed may be at most 3 Mhz when calling this function.*
+    pub fn enter_sleep_for(
+        self,
+        irq_on_wakeup: bool,
+        tick: SleepTick,
+        duration: core::time::Duration,
+        wake_source: WakeSource,
+    ) -> Result<DW1000<SPI, Sleeping>, Error<SPI>> {
+        let sleep_tim = tick.ticks_for(duration);
+        self.enter_sleep(irq_on_wakeup, Some(sleep_tim), wake_source)
+    }
+
+    /// Sends a frame, waits for the hardware auto-ACK, and retries on timeout
+    ///
+    /// This builds a reliable-delivery loop on top of the one-shot
+    /// [`send`]/[`DW1000::continue_receiving`] primitives: it sends `data`
+    /// to `destination` with the frame's ACK-request bit set, then switches
+    /// over to receiving (as [`TxContinuation::Rx`] already allows) to wait
+    /// for the remote's auto-ACK. If no ACK arrives within
+    /// `retry_config.ack_timeout_polls` polls, the frame is retransmitted,
+    /// waiting `retry_config.retry_backoff_us` in between, up to
+    /// `retry_config.max_attempts` times in total.
+    ///
+    /// Returns the radio in the `Ready` state, plus the number of attempts
+    /// that were made, so callers can log link quality. Gives up with
+    /// [`Error::MaxRetriesExceeded`] once the retry budget is exhausted.
+    ///
+    /// [`send`]: DW1000::send
+    pub fn send_with_retries<D>(
+        mut self,
+        delay: &mut D,
+        data: &[u8],
+        destination: mac::Address,
+        config: TxConfig,
+        retry_config: RetryConfig,
+    ) -> Result<(DW1000<SPI, Ready>, u8), Error<SPI>>
+    where
+        D: DelayNs,
+    {
+        let mut config = config;
+        config.continuation = TxContinuation::Rx {
+            frame_filtering: true,
+            auto_ack: AutoAck::Enabled {
+                turnaround_time: retry_config.ack_turnaround_time,
+            },
+        };
+
+        let mut ack_buffer = [0; 128];
+        let mut attempts: u8 = 0;
+
+        loop {
+            attempts += 1;
+
+            let seq = self.next_seq();
+            let frame = mac::Frame {
+                header: mac::Header {
+                    frame_type: mac::FrameType::Data,
+                    version: mac::FrameVersion::Ieee802154_2006,
+                    auxiliary_security_header: None,
+                    ie_present: false,
+                    seq_no_suppress: false,
+                    frame_pending: false,
+                    ack_request: true,
+                    pan_id_compress: false,
+                    destination: Some(destination),
+                    source: Some(self.get_address()?),
+                    seq,
+                },
+                content: mac::FrameContent::Data,
+                payload: data,
+                footer: [0; 2],
+            };
+
+            let mut sending = self.send_raw(
+                |buffer| {
+                    let mut len = 0;
+                    let result = buffer.write_with(
+                        &mut len,
+                        frame,
+                        &mut FrameSerDesContext::no_security(FooterMode::None),
+                    );
+
+                    if let Err(err) = result {
+                        panic!("Failed to write frame: {:?}", err);
+                    }
+
+                    len
+                },
+                SendTime::Now,
+                config,
+            )?;
+
+            nb::block!(sending.wait_transmit())?;
+
+            let mut receiving = match sending.continue_receiving() {
+                Ok(receiving) => receiving,
+                Err((_, error)) => return Err(error),
+            };
+
+            // Polls for the ACK we just requested, ignoring any frame that
+            // isn't actually it (a stray frame from another device on the
+            // channel shouldn't be mistaken for our ACK). Resolves to
+            // `Error::AckTimeout` once the poll budget for this attempt is
+            // exhausted; that doesn't necessarily mean the overall send has
+            // failed, since there may be attempts left to retry with.
+            let ack_result: Result<(), Error<SPI>> = 'ack: {
+                for _ in 0..retry_config.ack_timeout_polls {
+                    match receiving.wait_receive(&mut ack_buffer) {
+                        Ok(message) => {
+                            let is_our_ack = message.header.frame_type
+                                == mac::FrameType::Acknowledgement
+                                && message.header.seq == seq;
+                            // `SingleBufferReceiving` is one-shot: once
+                            // `wait_receive` has returned `Ok` the receive is
+                            // over, whether or not the frame turned out to be
+                            // our ACK. Looping `wait_receive` again on this
+                            // same instance wouldn't pick up anything else,
+                            // so a non-matching frame ends this attempt
+                            // rather than keep polling a dead receiver.
+                            break 'ack if is_our_ack {
+                                Ok(())
+                            } else {
+                                Err(Error::AckTimeout)
+                            };
+                        }
+                        Err(nb::Error::WouldBlock) => {
+                            delay.delay_us(retry_config.ack_poll_delay_us);
+                        }
+                        Err(nb::Error::Other(error)) => break 'ack Err(error),
+                    }
+                }
+
+                Err(Error::AckTimeout)
+            };
+
+            let ready = match receiving.finish_receiving() {
+                Ok(ready) => ready,
+                Err((_, error)) => return Err(error),
+            };
+
+            match ack_result {
+                Ok(()) => return Ok((ready, attempts)),
+                Err(Error::AckTimeout) if attempts < retry_config.max_attempts => {
+                    // Still have attempts left; fall through and retry.
+                }
+                Err(Error::AckTimeout) => {
+                    return Err(Error::MaxRetriesExceeded { attempts })
+                }
+                Err(error) => return Err(error),
+            }
+
+            if retry_config.retry_backoff_us > 0 {
+                delay.delay_us(retry_config.retry_backoff_us);
+            }
+
+            self = ready;
+        }
+    }
+}
+
+/// Configuration for [`DW1000::send_with_retries`]
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    /// Number of times to send the frame before giving up. This includes the
+    /// initial attempt, so `1` never retries. Defaults to `3`, like the
+    /// retry counts used by other 802.15.4 drivers.
+    pub max_attempts: u8,
+
+    /// The auto-ACK turnaround time passed to [`AutoAck::Enabled`]
+    pub ack_turnaround_time: u8,
+
+    /// How many times to poll for the auto-ACK before considering it lost
+    pub ack_timeout_polls: u32,
+
+    /// Delay between each ACK poll, in microseconds
+    pub ack_poll_delay_us: u32,
+
+    /// Delay before retrying a frame that wasn't ACKed, in microseconds.
+    /// `0` disables the backoff.
+    pub retry_backoff_us: u32,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: 3,
+            ack_turnaround_time: 0,
+            ack_timeout_polls: 1_000,
+            ack_poll_delay_us: 100,
+            retry_backoff_us: 0,
+        }
+    }
 }
@@ -0,0 +1,262 @@
+use crate::{
+    configs::PulseRepetitionFrequency, hl::ready::WakeSource, Error, SingleBufferReceiving,
+    Sleeping, DW1000,
+};
+use embedded_hal::spi::SpiDevice;
+#[cfg(feature = "async")]
+use nb;
+
+impl<SPI> DW1000<SPI, SingleBufferReceiving>
+where
+    SPI: SpiDevice,
+{
+    /// Reads the DW1000's diagnostic registers and derives a quality report
+    /// for the frame that was just received.
+    ///
+    /// This needs to be called before the next receive operation is started,
+    /// as the diagnostic registers are only valid for the most recently
+    /// received frame.
+    pub fn rx_quality(&mut self) -> Result<RxQuality, Error<SPI>> {
+        let rx_finfo = self.ll.rx_finfo().read()?;
+        let rx_fqual = self.ll.rx_fqual().read()?;
+        let rx_time = self.ll.rx_time().read()?;
+
+        let prf_64mhz = self.state.config.pulse_repetition_frequency == PulseRepetitionFrequency::Mhz64;
+
+        RxQuality::calculate(
+            rx_finfo.rxpacc(),
+            rx_fqual.cir_pwr(),
+            rx_time.fp_ampl1(),
+            rx_fqual.fp_ampl2(),
+            rx_fqual.fp_ampl3(),
+            prf_64mhz,
+        )
+    }
+
+    /// Finishes a receive that was configured to sleep automatically via
+    /// [`DW1000::configure_auto_sleep`]
+    ///
+    /// This is the receiving counterpart to
+    /// [`DW1000<SPI, Sending>::finish_sending_to_sleep`][sending]: by the
+    /// time a frame has actually been received, the PMSC has already loaded
+    /// the AON array and dropped the chip into sleep by itself, so this just
+    /// reflects that in the typestate instead of trying to reset the
+    /// transceiver back to `Ready`, without issuing any SPI transaction of
+    /// its own against a chip that may already be asleep.
+    /// `tx_antenna_delay` and `wake_source` should be exactly what
+    /// `configure_auto_sleep` returned and was passed, respectively, so they
+    /// can be recorded on the resulting [`Sleeping`] state.
+    ///
+    /// [sending]: crate::hl::Sending
+    #[allow(clippy::type_complexity)]
+    pub fn finish_receiving_to_sleep(
+        self,
+        tx_antenna_delay: u16,
+        wake_source: WakeSource,
+    ) -> Result<DW1000<SPI, Sleeping>, (Self, Error<SPI>)> {
+        if !self.state.finished {
+            return Err((self, Error::RxNotFinished));
+        }
+
+        Ok(DW1000 {
+            ll: self.ll,
+            seq: self.seq,
+            state: Sleeping {
+                tx_antenna_delay,
+                wake_source,
+            },
+        })
+    }
+}
+
+/// A signal-quality report for a received frame
+///
+/// Derived from the DW1000's diagnostic registers, as described in the user
+/// manual, section 4.7.2. Carries a calibrated receive power, plus a
+/// confidence level for whether the signal travelled a line-of-sight path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RxQuality {
+    rssi: f32,
+    los_confidence_level: f32,
+}
+
+impl RxQuality {
+    /// Calculates the RX quality from the raw diagnostic register values
+    ///
+    /// - `rx_preamble_count` is `RXPACC` from `RX_FINFO`, the number of
+    ///   preamble symbols accumulated.
+    /// - `rx_cir_pwr` is `CIR_PWR` from `RX_FQUAL`, the channel impulse
+    ///   response power.
+    /// - `fp_ampl1`, `fp_ampl2` and `fp_ampl3` are the first path amplitude
+    ///   points 1 through 3, from `RX_TIME` and `RX_FQUAL` respectively.
+    /// - `prf_64mhz` indicates whether the receiver was configured for a
+    ///   64 MHz pulse repetition frequency, as opposed to 16 MHz. This
+    ///   selects the correction constant used in the power calculations.
+    pub(super) fn calculate<SPI>(
+        rx_preamble_count: u16,
+        rx_cir_pwr: u16,
+        fp_ampl1: u16,
+        fp_ampl2: u16,
+        fp_ampl3: u16,
+        prf_64mhz: bool,
+    ) -> Result<Self, Error<SPI>>
+    where
+        SPI: SpiDevice,
+    {
+        if rx_preamble_count == 0 {
+            return Err(Error::BadRssiCalculation);
+        }
+
+        // See user manual, section 4.7.2, formulas for RX and FP power.
+        let a = if prf_64mhz { 121.74 } else { 113.77 };
+        let n = rx_preamble_count as f32;
+
+        let rx_power = 10.0 * log10(rx_cir_pwr as f32 * (1u32 << 17) as f32 / (n * n)) - a;
+
+        let fp_power = 10.0
+            * log10(
+                (fp_ampl1 as f32 * fp_ampl1 as f32
+                    + fp_ampl2 as f32 * fp_ampl2 as f32
+                    + fp_ampl3 as f32 * fp_ampl3 as f32)
+                    / (n * n),
+            )
+            - a;
+
+        // The difference between the receive power and the first path power
+        // indicates how much energy arrived after the first path, which is a
+        // good indicator for a non-line-of-sight signal. See user manual,
+        // section 4.7.1.
+        let d = rx_power - fp_power;
+        let los_confidence_level = if d <= 6.0 {
+            1.0
+        } else if d >= 10.0 {
+            0.0
+        } else {
+            1.0 - (d - 6.0) / 4.0
+        };
+
+        Ok(RxQuality {
+            rssi: rx_power,
+            los_confidence_level,
+        })
+    }
+
+    /// The calibrated receive signal strength, in dBm
+    pub fn rssi(&self) -> f32 {
+        self.rssi
+    }
+
+    /// The confidence that the received frame travelled a line-of-sight path
+    ///
+    /// `1.0` indicates strong confidence in line-of-sight, `0.0` indicates
+    /// strong confidence in a non-line-of-sight path. Values in between are
+    /// linearly interpolated.
+    pub fn los_confidence_level(&self) -> f32 {
+        self.los_confidence_level
+    }
+}
+
+// `core` doesn't provide a base-10 logarithm for `f32` on all targets, so we
+// implement it in terms of the natural logarithm, which `libm` always does.
+fn log10(value: f32) -> f32 {
+    libm::logf(value) / libm::logf(10.0)
+}
+
+#[cfg(test)]
+mod rx_quality_tests {
+    use super::RxQuality;
+    use crate::Error;
+    use embedded_hal::spi::{ErrorKind, ErrorType, Operation, SpiDevice};
+
+    // `RxQuality::calculate` never touches the SPI bus; this only exists to
+    // give `Error<SPI>` a concrete type to name in these tests.
+    struct NoSpi;
+
+    impl ErrorType for NoSpi {
+        type Error = ErrorKind;
+    }
+
+    impl SpiDevice for NoSpi {
+        fn transaction(&mut self, _operations: &mut [Operation<'_, u8>]) -> Result<(), ErrorKind> {
+            unreachable!("RxQuality::calculate never touches the SPI bus")
+        }
+    }
+
+    #[test]
+    fn zero_preamble_count_is_a_bad_rssi_calculation() {
+        let result: Result<RxQuality, Error<NoSpi>> = RxQuality::calculate(0, 1000, 100, 100, 100, true);
+        assert!(matches!(result, Err(Error::BadRssiCalculation)));
+    }
+
+    #[test]
+    fn matching_rx_and_first_path_power_is_full_los_confidence() {
+        // Chosen so the receive power and first path power are within
+        // fractions of a dB of each other (d ~= 0), comfortably inside the
+        // `d <= 6.0` branch.
+        let quality: RxQuality =
+            RxQuality::calculate::<NoSpi>(10, 1, 362, 0, 0, true).unwrap();
+
+        assert!((quality.rssi() - (-90.56)).abs() < 0.1);
+        assert_eq!(quality.los_confidence_level(), 1.0);
+    }
+
+    #[test]
+    fn first_path_power_far_below_rx_power_is_zero_los_confidence() {
+        // Here the first path power is much lower than the receive power
+        // (d > 10.0), indicating a non-line-of-sight path.
+        let quality: RxQuality =
+            RxQuality::calculate::<NoSpi>(10, 1, 100, 0, 0, true).unwrap();
+
+        assert_eq!(quality.los_confidence_level(), 0.0);
+    }
+
+    #[test]
+    fn interpolates_between_the_los_thresholds() {
+        // `d` lands between the two thresholds for a handful of `fp_ampl1`
+        // values around the boundary; confidence should decrease
+        // monotonically as the first path power drops further below the
+        // receive power.
+        let mut previous = 1.0;
+        for fp_ampl1 in [180, 165, 150, 135, 120] {
+            let quality: RxQuality =
+                RxQuality::calculate::<NoSpi>(10, 1, fp_ampl1, 0, 0, true).unwrap();
+            let confidence = quality.los_confidence_level();
+
+            assert!((0.0..=1.0).contains(&confidence));
+            assert!(confidence <= previous);
+            previous = confidence;
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<SPI> DW1000<SPI, SingleBufferReceiving>
+where
+    SPI: SpiDevice,
+{
+    /// Waits asynchronously for a message to be received
+    ///
+    /// This is the non-blocking counterpart to `wait_receive`. Instead of
+    /// busy-polling `SYS_STATUS`, it awaits `irq` (see
+    /// [`super::sending::IrqSource`]) and only reads the status register once
+    /// woken up. Make sure to call [`DW1000::enable_rx_interrupts`] beforehand,
+    /// so the IRQ line actually gets asserted once a frame has arrived.
+    pub async fn wait_receive_async<'b, IRQ>(
+        &mut self,
+        buffer: &'b mut [u8],
+        irq: &mut IRQ,
+    ) -> Result<super::Message<'b>, Error<SPI>>
+    where
+        IRQ: super::sending::IrqSource,
+    {
+        loop {
+            irq.wait_for_irq().await;
+
+            match self.wait_receive(buffer) {
+                Err(nb::Error::WouldBlock) => continue,
+                Err(nb::Error::Other(error)) => return Err(error),
+                Ok(message) => return Ok(message),
+            }
+        }
+    }
+}
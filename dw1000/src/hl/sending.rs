@@ -1,4 +1,7 @@
-use crate::{configs::TxContinuation, time::Instant, Error, Ready, RxConfig, Sending, DW1000};
+use crate::{
+    configs::TxContinuation, hl::ready::WakeSource, time::Instant, Error, Ready, RxConfig, Sending,
+    Sleeping, DW1000,
+};
 use embedded_hal::spi::SpiDevice;
 use nb;
 
@@ -126,6 +129,38 @@ where
         })
     }
 
+    /// Finishes a send that was configured to sleep automatically via
+    /// [`DW1000::configure_auto_sleep`]
+    ///
+    /// Unlike [`Self::finish_sending`], this doesn't try to reset the
+    /// transceiver back to `Ready`: by the time [`Self::wait_transmit`]
+    /// reports completion, the PMSC has already loaded the AON array and
+    /// dropped the chip into sleep by itself, so this just reflects that in
+    /// the typestate, without issuing any SPI transaction of its own against
+    /// a chip that may already be asleep. `tx_antenna_delay` and
+    /// `wake_source` should be exactly what `configure_auto_sleep` returned
+    /// and was passed, respectively, so they can be recorded on the
+    /// resulting [`Sleeping`] state.
+    #[allow(clippy::type_complexity)]
+    pub fn finish_sending_to_sleep(
+        self,
+        tx_antenna_delay: u16,
+        wake_source: WakeSource,
+    ) -> Result<DW1000<SPI, Sleeping>, (Self, Error<SPI>)> {
+        if !self.state.finished {
+            return Err((self, Error::TxNotFinishedyet));
+        }
+
+        Ok(DW1000 {
+            ll: self.ll,
+            seq: self.seq,
+            state: Sleeping {
+                tx_antenna_delay,
+                wake_source,
+            },
+        })
+    }
+
     /// Continue on in the receiving state.
     ///
     /// This can only be called when the tx config specified this should be the continuation.
@@ -230,3 +265,45 @@ where
         Ok(())
     }
 }
+
+/// A source of DW1000 IRQ notifications, used to drive the async API
+///
+/// This is deliberately minimal, so that it can be implemented in terms of
+/// whatever interrupt-driven waker the host HAL provides for the DW1000's
+/// IRQ pin (an `exti` future, an RTIC resource, ...). Implementations are
+/// expected to resolve once the IRQ line has gone active at least once since
+/// the last call; they don't need to know anything about which event fired.
+#[cfg(feature = "async")]
+pub trait IrqSource {
+    /// Waits until the DW1000 IRQ line has become active
+    async fn wait_for_irq(&mut self);
+}
+
+#[cfg(feature = "async")]
+impl<SPI> DW1000<SPI, Sending>
+where
+    SPI: SpiDevice,
+{
+    /// Waits asynchronously for the transmission to finish
+    ///
+    /// This is the non-blocking counterpart to [`Self::wait_transmit`]. Instead
+    /// of busy-polling `SYS_STATUS`, it awaits `irq` (see [`IrqSource`]) and
+    /// only then reads the status register, so the executor can put the MCU
+    /// to sleep while the transmission is in progress. Make sure to call
+    /// [`DW1000::enable_tx_interrupts`] beforehand, so the IRQ line actually
+    /// gets asserted once the frame has been sent.
+    pub async fn wait_transmit_async<IRQ>(&mut self, irq: &mut IRQ) -> Result<Instant, Error<SPI>>
+    where
+        IRQ: IrqSource,
+    {
+        loop {
+            irq.wait_for_irq().await;
+
+            match self.wait_transmit() {
+                Err(nb::Error::WouldBlock) => continue,
+                Err(nb::Error::Other(error)) => return Err(error),
+                Ok(instant) => return Ok(instant),
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Typed decoding of the DW1000's `SYS_MASK`/`SYS_STATUS` interrupt events
+//!
+//! [`DW1000::configure_interrupts`] uses [`IrqMask`] to control which events
+//! the DW1000 asserts its IRQ line for, writing it straight to `SYS_MASK`.
+//! [`DW1000::read_events`] reads `SYS_STATUS` and decodes it into
+//! [`IrqEvents`], and [`DW1000::clear_events`] writes it back, clearing
+//! exactly the acknowledged bits (`SYS_STATUS` is write-1-to-clear). Because
+//! both registers share the same bit layout, a single flag type, [`Irq`],
+//! serves as both the "which to enable" mask and the "which fired" event
+//! set.
+//!
+//! [`DW1000::configure_interrupts`]: crate::DW1000::configure_interrupts
+//! [`DW1000::read_events`]: crate::DW1000::read_events
+//! [`DW1000::clear_events`]: crate::DW1000::clear_events
+
+use bitflags::bitflags;
+
+bitflags! {
+    /// A set of DW1000 `SYS_MASK`/`SYS_STATUS` interrupt events
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Irq: u32 {
+        /// Clock PLL lock (`CPLOCK`)
+        const CLOCK_PLL_LOCK = 1 << 1;
+        /// TX frame sent (`TXFRS`)
+        const TX_FRAME_SENT = 1 << 7;
+        /// PHY header error (`RXPHE`)
+        const RX_PHY_HEADER_ERROR = 1 << 12;
+        /// Data frame ready (`RXDFR`)
+        const RX_DATA_FRAME_READY = 1 << 13;
+        /// Receiver FCS error (`RXFCE`)
+        const RX_FCS_ERROR = 1 << 15;
+        /// Receiver Reed-Solomon frame sync loss (`RXRFSL`)
+        const RX_REED_SOLOMON_SYNC_LOSS = 1 << 16;
+        /// Receive frame wait timeout (`RXRFTO`)
+        const RX_TIMEOUT = 1 << 17;
+        /// Receiver overrun (`RXOVRR`)
+        const RX_OVERRUN = 1 << 20;
+        /// Preamble detection timeout (`RXPTO`)
+        const RX_PREAMBLE_TIMEOUT = 1 << 21;
+        /// Sleep-to-init (`SLP2INIT`)
+        const SLEEP_TO_INIT = 1 << 23;
+        /// Receiver SFD timeout (`RXSFDTO`)
+        const RX_SFD_TIMEOUT = 1 << 26;
+        /// Automatic frame filtering rejection (`AFFREJ`)
+        const AUTOMATIC_FRAME_FILTER_REJECTION = 1 << 29;
+    }
+}
+
+/// The set of events the DW1000 should assert its IRQ line for, as written
+/// to `SYS_MASK`
+pub type IrqMask = Irq;
+
+/// The set of events that have fired, as read from `SYS_STATUS`
+pub type IrqEvents = Irq;
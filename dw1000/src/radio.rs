@@ -0,0 +1,292 @@
+//! Implementation of the generic [`radio`] crate traits
+//!
+//! These let application code target the DW1000 the same way it would any
+//! other driver that implements `radio` (for example `sx128x`), instead of
+//! using the DW1000-specific typestate API directly.
+//!
+//! The `radio` traits assume a single type whose state lives internally,
+//! while [`DW1000`] encodes its state in the type itself. [`Dw1000Radio`]
+//! bridges the two by wrapping the typestates in an enum and moving between
+//! them as the trait methods are called.
+
+#![cfg(feature = "radio")]
+
+use crate::{
+    hl::{Ready, Sending, SingleBufferReceiving},
+    Error, RxConfig, RxQuality, SendTime, TxConfig, DW1000,
+};
+use embedded_hal::spi::SpiDevice;
+use radio::{Busy, Interrupts, Receive, Rssi, State as RadioState, Transmit};
+
+/// The state of a [`Dw1000Radio`], as seen through `radio`'s generic
+/// [`RadioState`] trait
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dw1000State {
+    /// Radio is idle; a transmission or receive can be started
+    Ready,
+    /// A frame is currently being transmitted
+    Transmitting,
+    /// The radio is waiting to receive a frame
+    Receiving,
+}
+
+enum Inner<SPI>
+where
+    SPI: SpiDevice,
+{
+    Ready(DW1000<SPI, Ready>),
+    Sending(DW1000<SPI, Sending>),
+    Receiving(DW1000<SPI, SingleBufferReceiving>),
+    /// Held while a method is moving between the variants above. It stays
+    /// in place if starting a transmit or receive fails, since
+    /// `DW1000::send`/`DW1000::receive` don't hand the driver back on error;
+    /// from then on, every method that needs the driver returns
+    /// `Error::InvalidConfiguration`.
+    Invalid,
+}
+
+/// Adapts the typestate-based [`DW1000`] driver to the generic [`radio`]
+/// crate traits
+pub struct Dw1000Radio<SPI>
+where
+    SPI: SpiDevice,
+{
+    inner: Inner<SPI>,
+    tx_config: TxConfig,
+    rx_config: RxConfig,
+}
+
+impl<SPI> Dw1000Radio<SPI>
+where
+    SPI: SpiDevice,
+{
+    /// Wraps an already initialized DW1000 for use with the `radio` traits
+    ///
+    /// `tx_config` and `rx_config` are used for every [`Transmit`]/[`Receive`]
+    /// call made through this wrapper; use the typestate API directly if you
+    /// need per-call configuration.
+    pub fn new(dw1000: DW1000<SPI, Ready>, tx_config: TxConfig, rx_config: RxConfig) -> Self {
+        Dw1000Radio {
+            inner: Inner::Ready(dw1000),
+            tx_config,
+            rx_config,
+        }
+    }
+
+}
+
+impl<SPI> Transmit for Dw1000Radio<SPI>
+where
+    SPI: SpiDevice,
+{
+    type Error = Error<SPI>;
+
+    fn start_transmit(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        let dw1000 = match core::mem::replace(&mut self.inner, Inner::Invalid) {
+            Inner::Ready(dw1000) => dw1000,
+            other => {
+                self.inner = other;
+                return Err(Error::InvalidConfiguration);
+            }
+        };
+
+        match dw1000.send(data, None, SendTime::Now, self.tx_config) {
+            Ok(sending) => {
+                self.inner = Inner::Sending(sending);
+                Ok(())
+            }
+            Err(error) => {
+                // Unlike `finish_sending`/`abort_sending`, `DW1000::send`
+                // doesn't hand `self` back on error, so there's nothing to
+                // restore the wrapper to. Leave `inner` as `Invalid`, so
+                // later calls fail loudly instead of silently operating on
+                // a radio that may be mid-transaction.
+                Err(error)
+            }
+        }
+    }
+
+    fn check_transmit(&mut self) -> Result<bool, Self::Error> {
+        let sending = match core::mem::replace(&mut self.inner, Inner::Invalid) {
+            Inner::Sending(sending) => sending,
+            other => {
+                self.inner = other;
+                return Err(Error::InvalidConfiguration);
+            }
+        };
+
+        let mut sending = sending;
+        match sending.wait_transmit() {
+            Ok(_instant) => match sending.finish_sending() {
+                Ok(ready) => {
+                    self.inner = Inner::Ready(ready);
+                    Ok(true)
+                }
+                Err((sending, error)) => {
+                    self.inner = Inner::Sending(sending);
+                    Err(error)
+                }
+            },
+            Err(nb::Error::WouldBlock) => {
+                self.inner = Inner::Sending(sending);
+                Ok(false)
+            }
+            Err(nb::Error::Other(error)) => {
+                self.inner = Inner::Sending(sending);
+                Err(error)
+            }
+        }
+    }
+}
+
+impl<SPI> Receive for Dw1000Radio<SPI>
+where
+    SPI: SpiDevice,
+{
+    type Error = Error<SPI>;
+    type Info = RxQuality;
+
+    fn start_receive(&mut self) -> Result<(), Self::Error> {
+        let dw1000 = match core::mem::replace(&mut self.inner, Inner::Invalid) {
+            Inner::Ready(dw1000) => dw1000,
+            other => {
+                self.inner = other;
+                return Err(Error::InvalidConfiguration);
+            }
+        };
+
+        match dw1000.receive(self.rx_config) {
+            Ok(receiving) => {
+                self.inner = Inner::Receiving(receiving);
+                Ok(())
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    fn check_receive(&mut self, restart: bool) -> Result<bool, Self::Error> {
+        match &mut self.inner {
+            Inner::Receiving(dw1000) => {
+                let sys_status = dw1000.ll().sys_status().read()?;
+                Ok(sys_status.rxdfr() == 0b1)
+            }
+            _ if restart => {
+                self.start_receive()?;
+                Ok(false)
+            }
+            _ => Err(Error::InvalidConfiguration),
+        }
+    }
+
+    fn get_received(&mut self, buff: &mut [u8]) -> Result<(usize, Self::Info), Self::Error> {
+        let mut receiving = match core::mem::replace(&mut self.inner, Inner::Invalid) {
+            Inner::Receiving(receiving) => receiving,
+            other => {
+                self.inner = other;
+                return Err(Error::InvalidConfiguration);
+            }
+        };
+
+        // Read the frame before asking for its quality: `rx_quality` relies
+        // on diagnostics (`RX_FQUAL`/`RX_FINFO`) that are only valid once a
+        // frame has actually landed, and reading it first also means a
+        // `WouldBlock` here doesn't need to touch the diagnostics registers
+        // at all.
+        let message = match receiving.wait_receive(buff) {
+            Ok(message) => message,
+            Err(nb::Error::WouldBlock) => {
+                self.inner = Inner::Receiving(receiving);
+                return Err(Error::RxNotFinished);
+            }
+            Err(nb::Error::Other(error)) => {
+                self.inner = Inner::Receiving(receiving);
+                return Err(error);
+            }
+        };
+        let len = message.payload.len();
+
+        let quality = match receiving.rx_quality() {
+            Ok(quality) => quality,
+            Err(error) => {
+                self.inner = Inner::Receiving(receiving);
+                return Err(error);
+            }
+        };
+
+        match receiving.finish_receiving() {
+            Ok(ready) => self.inner = Inner::Ready(ready),
+            Err((receiving, error)) => {
+                self.inner = Inner::Receiving(receiving);
+                return Err(error);
+            }
+        }
+
+        Ok((len, quality))
+    }
+}
+
+impl<SPI> RadioState for Dw1000Radio<SPI>
+where
+    SPI: SpiDevice,
+{
+    type State = Dw1000State;
+    type Error = Error<SPI>;
+
+    fn set_state(&mut self, _state: Self::State) -> Result<(), Self::Error> {
+        // State transitions on the DW1000 are driven by the typestate
+        // methods, each of which requires additional arguments (config,
+        // data, ...) that don't fit this trait's signature.
+        Err(Error::InvalidConfiguration)
+    }
+
+    fn get_state(&mut self) -> Result<Self::State, Self::Error> {
+        Ok(match self.inner {
+            Inner::Ready(_) => Dw1000State::Ready,
+            Inner::Sending(_) => Dw1000State::Transmitting,
+            Inner::Receiving(_) => Dw1000State::Receiving,
+            Inner::Invalid => return Err(Error::InvalidConfiguration),
+        })
+    }
+}
+
+impl<SPI> Rssi for Dw1000Radio<SPI>
+where
+    SPI: SpiDevice,
+{
+    type Error = Error<SPI>;
+
+    fn poll_rssi(&mut self) -> Result<i16, Self::Error> {
+        match &mut self.inner {
+            Inner::Receiving(receiving) => Ok(receiving.rx_quality()?.rssi() as i16),
+            _ => Err(Error::InvalidConfiguration),
+        }
+    }
+}
+
+impl<SPI> Busy for Dw1000Radio<SPI>
+where
+    SPI: SpiDevice,
+{
+    type Error = Error<SPI>;
+
+    fn is_busy(&mut self) -> Result<bool, Self::Error> {
+        Ok(!matches!(self.inner, Inner::Ready(_)))
+    }
+}
+
+impl<SPI> Interrupts for Dw1000Radio<SPI>
+where
+    SPI: SpiDevice,
+{
+    type Irq = u32;
+    type Error = Error<SPI>;
+
+    fn get_interrupts(&mut self) -> Result<Self::Irq, Self::Error> {
+        match &mut self.inner {
+            Inner::Ready(dw1000) => Ok(dw1000.ll().sys_status().read()?.raw_value()),
+            Inner::Sending(dw1000) => Ok(dw1000.ll().sys_status().read()?.raw_value()),
+            Inner::Receiving(dw1000) => Ok(dw1000.ll().sys_status().read()?.raw_value()),
+            Inner::Invalid => Err(Error::InvalidConfiguration),
+        }
+    }
+}